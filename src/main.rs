@@ -4,6 +4,10 @@ use bevy::{
         interpolation::{utils::lerp_unclamped, Interpolation},
     },
     prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::Image,
+    },
 };
 use bevy_egui::{egui, EguiContext, EguiPlugin};
 
@@ -14,14 +18,143 @@ enum TangentEdit {
     Out,
 }
 
+// Maximum number of snapshots kept on each of the undo/redo stacks.
+const MAX_HISTORY_DEPTH: usize = 128;
+
+// Smallest time/value span `zoom_to_fit` will frame, so a flat or single keyframe
+// curve doesn't collapse the view to zero width/height.
+const MIN_ZOOM_TO_FIT_SPAN: f32 = 1.0;
+
+// A single keyframe copied to the clipboard, holding everything needed to reinsert it elsewhere:
+// its coordinates, interpolation, and tangents.
+#[derive(Clone)]
+struct CopiedKeyframe {
+    time: f32,
+    value: f32,
+    interpolation: Interpolation,
+    tangent_control: TangentControl,
+    in_tangent: f32,
+    out_tangent: f32,
+}
+
+// A single sine term of an additive waveform: `amplitude * sin(2*pi*harmonic/period*t + phase)`.
+#[derive(Clone)]
+struct Harmonic {
+    amplitude: f32,
+    phase: f32,
+}
+
+// A single named, colored animation track. `CurveEditor` holds a list of these so
+// several channels can be authored in one viewport, as in Godot and fyrox's editors.
+struct CurveChannel {
+    name: String,
+    color: egui::Color32,
+    visible: bool,
+    curve: CurveVariable<f32>,
+}
+
 struct CurveEditor {
     dragging: bool,
     selected_keyframe: usize,
     display_offset: Vec2,
     display_range: Vec2,
-    curve: CurveVariable<f32>,
+    channels: Vec<CurveChannel>,
+    active_channel: usize,
     tangent_popup_position: egui::Pos2,
     tangent_drag: TangentEdit,
+    // Each snapshot is tagged with the channel it was taken from, so undo/redo always
+    // writes back to that channel instead of whichever channel happens to be active.
+    undo_stack: Vec<(usize, CurveVariable<f32>)>,
+    redo_stack: Vec<(usize, CurveVariable<f32>)>,
+    baked_lookup_texture: Option<Handle<Image>>,
+    min_value: Option<f32>,
+    max_value: Option<f32>,
+    clipboard_keyframe: Option<CopiedKeyframe>,
+    clipboard_curve: Option<CurveVariable<f32>>,
+    harmonics: Vec<Harmonic>,
+    harmonic_period: f32,
+}
+
+impl CurveEditor {
+    fn undo(&mut self) {
+        if let Some((channel, curve)) = self.undo_stack.pop() {
+            if channel >= self.channels.len() {
+                return;
+            }
+
+            let active = &mut self.channels[channel].curve;
+            self.redo_stack.push((channel, std::mem::replace(active, curve)));
+            self.active_channel = channel;
+            self.selected_keyframe = usize::MAX;
+            self.dragging = false;
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some((channel, curve)) = self.redo_stack.pop() {
+            if channel >= self.channels.len() {
+                return;
+            }
+
+            let active = &mut self.channels[channel].curve;
+            self.undo_stack.push((channel, std::mem::replace(active, curve)));
+            self.active_channel = channel;
+            self.selected_keyframe = usize::MAX;
+            self.dragging = false;
+        }
+    }
+
+    // Recomputes `display_offset`/`display_range` so every keyframe of the active
+    // channel is visible, mirroring fyrox-ui's `ZoomToFit` message.
+    fn zoom_to_fit(&mut self) {
+        let curve = &self.channels[self.active_channel].curve;
+        if curve.len() == 0 {
+            self.display_offset = Vec2::new(0.0, -0.5);
+            self.display_range = Vec2::new(2.0, 3.5);
+            return;
+        }
+
+        let mut min_t = f32::MAX;
+        let mut max_t = f32::MIN;
+        let mut min_v = f32::MAX;
+        let mut max_v = f32::MIN;
+
+        for i in 0..curve.len() {
+            let t = curve.get_time(i as CurveCursor);
+            let v = *curve.get_value(i as CurveCursor);
+
+            min_t = min_t.min(t);
+            max_t = max_t.max(t);
+            min_v = min_v.min(v);
+            max_v = max_v.max(v);
+        }
+
+        let t_span = (max_t - min_t).max(MIN_ZOOM_TO_FIT_SPAN);
+        let v_span = (max_v - min_v).max(MIN_ZOOM_TO_FIT_SPAN);
+
+        let pad_x = t_span * 0.1;
+        let pad_y = v_span * 0.1;
+
+        self.display_offset = Vec2::new(min_t - pad_x, min_v - pad_y);
+        self.display_range = Vec2::new(t_span + 2.0 * pad_x, v_span + 2.0 * pad_y);
+    }
+}
+
+// Snapshots `curve` (tagged with the channel it belongs to) onto `undo_stack` and
+// clears `redo_stack`, this should be called right before a discrete edit (drag start,
+// insert, delete, tangent change) is applied so the snapshot represents the state to
+// come back to.
+fn push_undo(
+    channel: usize,
+    curve: &CurveVariable<f32>,
+    undo_stack: &mut Vec<(usize, CurveVariable<f32>)>,
+    redo_stack: &mut Vec<(usize, CurveVariable<f32>)>,
+) {
+    if undo_stack.len() == MAX_HISTORY_DEPTH {
+        undo_stack.remove(0);
+    }
+    undo_stack.push((channel, curve.clone()));
+    redo_stack.clear();
 }
 
 fn main() {
@@ -31,13 +164,28 @@ fn main() {
             selected_keyframe: usize::MAX,
             display_offset: Vec2::new(0.0, -0.5),
             display_range: Vec2::new(2.0, 3.5),
-            curve: CurveVariable::with_auto_tangents(
-                vec![0.0, 1.0, 1.3, 1.6, 1.7, 1.8, 1.9, 2.0],
-                vec![3.0, 0.0, 1.0, 0.0, 0.5, 0.0, 0.25, 0.0],
-            )
-            .unwrap(),
+            channels: vec![CurveChannel {
+                name: "Channel 0".to_string(),
+                color: egui::Color32::RED,
+                visible: true,
+                curve: CurveVariable::with_auto_tangents(
+                    vec![0.0, 1.0, 1.3, 1.6, 1.7, 1.8, 1.9, 2.0],
+                    vec![3.0, 0.0, 1.0, 0.0, 0.5, 0.0, 0.25, 0.0],
+                )
+                .unwrap(),
+            }],
+            active_channel: 0,
             tangent_popup_position: (0.0, 0.0).into(),
             tangent_drag: TangentEdit::No,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            baked_lookup_texture: None,
+            min_value: None,
+            max_value: None,
+            clipboard_keyframe: None,
+            clipboard_curve: None,
+            harmonics: Vec::new(),
+            harmonic_period: 1.0,
         })
         .add_plugins(DefaultPlugins)
         .add_plugin(EguiPlugin)
@@ -51,6 +199,42 @@ fn remap(min: f32, max: f32, t: f32, out_min: f32, out_max: f32) -> f32 {
     lerp_unclamped(out_min, out_max, n)
 }
 
+#[inline]
+fn clamp_value(min_value: Option<f32>, max_value: Option<f32>, v: f32) -> f32 {
+    let v = min_value.map_or(v, |min_value| v.max(min_value));
+    max_value.map_or(v, |max_value| v.min(max_value))
+}
+
+// Picks a "nice" grid step (1/2/5 * 10^n) for `span` so roughly `target_lines` grid
+// lines are visible no matter the current zoom level.
+#[inline]
+fn nice_grid_step(span: f32, target_lines: f32) -> f32 {
+    if span <= 0.0 {
+        return 1.0;
+    }
+
+    let raw_step = span / target_lines;
+    let magnitude = 10f32.powf(raw_step.log10().floor());
+    let residual = raw_step / magnitude;
+
+    let nice_residual = if residual < 1.5 {
+        1.0
+    } else if residual < 3.0 {
+        2.0
+    } else if residual < 7.0 {
+        5.0
+    } else {
+        10.0
+    };
+
+    nice_residual * magnitude
+}
+
+#[inline]
+fn snap_to_grid(v: f32, step: f32) -> f32 {
+    (v / step).round() * step
+}
+
 #[inline]
 fn to_dir(a: f32) -> egui::Vec2 {
     // TODO: There's something wrong with this tangent generation
@@ -97,11 +281,335 @@ fn dot(
     }
 }
 
-fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiContext>) {
+// Color assigned to a newly added channel, cycling through a small fixed palette so
+// tracks stay visually distinct without the user having to pick one by hand.
+fn channel_color(index: usize) -> egui::Color32 {
+    const PALETTE: [egui::Color32; 6] = [
+        egui::Color32::RED,
+        egui::Color32::GREEN,
+        egui::Color32::BLUE,
+        egui::Color32::YELLOW,
+        egui::Color32::LIGHT_BLUE,
+        egui::Color32::from_rgb(255, 0, 255),
+    ];
+    PALETTE[index % PALETTE.len()]
+}
+
+// Default resolution of a baked lookup texture, matching Godot's `CurveTexture` default.
+const DEFAULT_LUT_RESOLUTION: usize = 256;
+
+// Number of samples taken across the generated waveform, each becoming one keyframe.
+// Kept small and relying on auto tangents to reconstruct the shape between them, so a
+// generated LFO curve stays a few dozen keyframes instead of thousands.
+const WAVEFORM_SAMPLE_COUNT: usize = 32;
+
+// Builds a curve from a sum of sine harmonics, `f(t) = sum(a_i * sin(2*pi*i*t/period +
+// p_i))`, sampled at `samples` evenly spaced times over `[min_t, max_t]`. Returns `None`
+// when `harmonics` is empty so callers can leave the active curve untouched instead of
+// replacing it with a flat line.
+fn generate_waveform(
+    harmonics: &[Harmonic],
+    period: f32,
+    min_t: f32,
+    max_t: f32,
+    samples: usize,
+) -> Option<CurveVariable<f32>> {
+    if harmonics.is_empty() || samples < 2 || period == 0.0 {
+        return None;
+    }
+
+    let mut times = Vec::with_capacity(samples);
+    let mut values = Vec::with_capacity(samples);
+
+    for i in 0..samples {
+        let t = lerp_unclamped(min_t, max_t, i as f32 / (samples - 1) as f32);
+
+        let v = harmonics
+            .iter()
+            .enumerate()
+            .map(|(h, harmonic)| {
+                let frequency = (h + 1) as f32 / period;
+                harmonic.amplitude * (std::f32::consts::TAU * frequency * t + harmonic.phase).sin()
+            })
+            .sum();
+
+        times.push(t);
+        values.push(v);
+    }
+
+    CurveVariable::with_auto_tangents(times, values)
+}
+
+// Samples `curve` at `resolution` uniformly spaced times across its authored range
+// using a single forward sweep with `sample_with_cursor`, so the cost is O(n +
+// resolution) instead of `resolution` independent binary searches.
+fn bake_curve_to_lut(curve: &CurveVariable<f32>, resolution: usize) -> Vec<f32> {
+    let len = curve.len();
+    if len == 0 || resolution == 0 {
+        return Vec::new();
+    }
+
+    let t0 = curve.get_time(0);
+    let t1 = curve.get_time((len - 1) as CurveCursor);
+
+    let mut samples = Vec::with_capacity(resolution);
+    let mut cursor = 0;
+    for i in 0..resolution {
+        let t = if resolution == 1 {
+            t0
+        } else {
+            lerp_unclamped(t0, t1, i as f32 / (resolution - 1) as f32)
+        };
+
+        let (next_cursor, v) = curve.sample_with_cursor(cursor, t);
+        cursor = next_cursor;
+        samples.push(v);
+    }
+
+    samples
+}
+
+// Bakes `curve` into a 1D R32Float lookup texture, like Godot's `CurveTexture`, so it
+// can be sampled directly by materials or particle systems as a ramp.
+fn bake_curve_to_image(curve: &CurveVariable<f32>, resolution: usize) -> Image {
+    let samples = bake_curve_to_lut(curve, resolution);
+    let data = samples
+        .iter()
+        .flat_map(|v| v.to_le_bytes().to_vec())
+        .collect::<Vec<u8>>();
+
+    Image::new(
+        Extent3d {
+            width: resolution as u32,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D1,
+        data,
+        TextureFormat::R32Float,
+    )
+}
+
+fn ui_example(
+    mut curve_editor: ResMut<CurveEditor>,
+    egui_context: Res<EguiContext>,
+    mut images: ResMut<Assets<Image>>,
+) {
     let curve_editor = &mut *curve_editor;
     egui::Window::new("Curve Editor")
         .default_size([700.0, 300.0])
         .show(egui_context.ctx(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Frame All (F)").clicked() {
+                    curve_editor.zoom_to_fit();
+                }
+
+                if ui.button("Bake to Texture").clicked() {
+                    let curve = &curve_editor.channels[curve_editor.active_channel].curve;
+                    let image = bake_curve_to_image(curve, DEFAULT_LUT_RESOLUTION);
+                    curve_editor.baked_lookup_texture = Some(images.add(image));
+                }
+
+                // Indicative value bounds, clamp dragging/insertion and draw guide lines
+                let mut has_min_value = curve_editor.min_value.is_some();
+                if ui.checkbox(&mut has_min_value, "Min").changed() {
+                    curve_editor.min_value = if has_min_value { Some(0.0) } else { None };
+                }
+                if let Some(min_value) = &mut curve_editor.min_value {
+                    ui.add(egui::DragValue::new(min_value).speed(0.01));
+                }
+
+                let mut has_max_value = curve_editor.max_value.is_some();
+                if ui.checkbox(&mut has_max_value, "Max").changed() {
+                    curve_editor.max_value = if has_max_value { Some(1.0) } else { None };
+                }
+                if let Some(max_value) = &mut curve_editor.max_value {
+                    ui.add(egui::DragValue::new(max_value).speed(0.01));
+                }
+            });
+
+            // Waveform generator: rebuild the active channel from a sum of sine
+            // harmonics, a fast way to seed oscillatory LFO-style curves.
+            ui.collapsing("Waveform Generator (additive harmonics)", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Period");
+                    ui.add(
+                        egui::DragValue::new(&mut curve_editor.harmonic_period)
+                            .speed(0.01)
+                            .clamp_range(0.001..=f32::MAX),
+                    );
+
+                    if ui.button("Add Harmonic").clicked() {
+                        curve_editor.harmonics.push(Harmonic {
+                            amplitude: 1.0,
+                            phase: 0.0,
+                        });
+                    }
+                });
+
+                let mut removed = None;
+                for (i, harmonic) in curve_editor.harmonics.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("#{}", i + 1));
+                        ui.label("Amplitude");
+                        ui.add(egui::DragValue::new(&mut harmonic.amplitude).speed(0.01));
+                        ui.label("Phase");
+                        ui.add(egui::DragValue::new(&mut harmonic.phase).speed(0.01));
+
+                        if ui.button("x").clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = removed {
+                    curve_editor.harmonics.remove(i);
+                }
+
+                if ui.button("Apply").clicked() {
+                    let min_t = curve_editor.display_offset.x;
+                    let max_t = min_t + curve_editor.display_range.x;
+
+                    let generated = generate_waveform(
+                        &curve_editor.harmonics,
+                        curve_editor.harmonic_period,
+                        min_t,
+                        max_t,
+                        WAVEFORM_SAMPLE_COUNT,
+                    );
+
+                    if let Some(generated) = generated {
+                        let active_channel = curve_editor.active_channel;
+                        let curve = &mut curve_editor.channels[active_channel].curve;
+                        push_undo(
+                            active_channel,
+                            curve,
+                            &mut curve_editor.undo_stack,
+                            &mut curve_editor.redo_stack,
+                        );
+                        *curve = generated;
+                        curve_editor.selected_keyframe = usize::MAX;
+                    }
+                }
+            });
+
+            // Channel legend: pick the active channel, toggle per-channel visibility,
+            // and add/remove channels so the editor can hold more than one track
+            {
+                let active_channel = curve_editor.active_channel;
+                let mut new_active_channel = None;
+                let mut removed_channel = None;
+
+                ui.horizontal(|ui| {
+                    for (i, channel) in curve_editor.channels.iter_mut().enumerate() {
+                        ui.checkbox(&mut channel.visible, "");
+                        if ui
+                            .selectable_label(active_channel == i, &channel.name)
+                            .clicked()
+                        {
+                            new_active_channel = Some(i);
+                        }
+                    }
+
+                    if ui.button("+ Channel").clicked() {
+                        let index = curve_editor.channels.len();
+                        curve_editor.channels.push(CurveChannel {
+                            name: format!("Channel {}", index),
+                            color: channel_color(index),
+                            visible: true,
+                            curve: CurveVariable::with_auto_tangents(
+                                vec![0.0, 1.0],
+                                vec![0.0, 0.0],
+                            )
+                            .unwrap(),
+                        });
+                        new_active_channel = Some(index);
+                    }
+
+                    if curve_editor.channels.len() > 1 && ui.button("- Channel").clicked() {
+                        removed_channel = Some(active_channel);
+                    }
+                });
+
+                if let Some(i) = new_active_channel {
+                    curve_editor.active_channel = i;
+                    curve_editor.selected_keyframe = usize::MAX;
+                }
+
+                if let Some(i) = removed_channel {
+                    curve_editor.channels.remove(i);
+                    curve_editor.active_channel =
+                        curve_editor.active_channel.min(curve_editor.channels.len() - 1);
+                    curve_editor.selected_keyframe = usize::MAX;
+
+                    // Snapshots tagged with the removed channel no longer apply, and
+                    // every channel after it shifts down by one index.
+                    curve_editor.undo_stack.retain(|(channel, _)| *channel != i);
+                    curve_editor.redo_stack.retain(|(channel, _)| *channel != i);
+                    for (channel, _) in curve_editor.undo_stack.iter_mut() {
+                        if *channel > i {
+                            *channel -= 1;
+                        }
+                    }
+                    for (channel, _) in curve_editor.redo_stack.iter_mut() {
+                        if *channel > i {
+                            *channel -= 1;
+                        }
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Name");
+                    let active_channel = curve_editor.active_channel;
+                    ui.text_edit_singleline(&mut curve_editor.channels[active_channel].name);
+                });
+            }
+
+            // Numeric entry for the selected keyframe, for precise coordinates instead
+            // of only dragging
+            {
+                let active_channel = curve_editor.active_channel;
+                let selected = curve_editor.selected_keyframe;
+                let curve = &mut curve_editor.channels[active_channel].curve;
+                let undo_stack = &mut curve_editor.undo_stack;
+                let redo_stack = &mut curve_editor.redo_stack;
+                let min_value = curve_editor.min_value;
+                let max_value = curve_editor.max_value;
+
+                if selected < curve.len() {
+                    let index = selected as CurveCursor;
+                    let mut t = curve.get_time(index);
+                    let mut v = *curve.get_value(index);
+                    let mut new_index = None;
+
+                    ui.horizontal(|ui| {
+                        ui.label("Time");
+                        let time_response = ui.add(egui::DragValue::new(&mut t).speed(0.01));
+                        if time_response.drag_started() || time_response.gained_focus() {
+                            // Discrete edit just started, snapshot once instead of on
+                            // every frame the value moves
+                            push_undo(active_channel, curve, undo_stack, redo_stack);
+                        }
+                        if time_response.changed() {
+                            new_index = curve.set_time(index, t);
+                        }
+
+                        ui.label("Value");
+                        let value_response = ui.add(egui::DragValue::new(&mut v).speed(0.01));
+                        if value_response.drag_started() || value_response.gained_focus() {
+                            push_undo(active_channel, curve, undo_stack, redo_stack);
+                        }
+                        if value_response.changed() {
+                            let v = clamp_value(min_value, max_value, v);
+                            curve.set_value(index, v);
+                        }
+                    });
+
+                    if let Some(k) = new_index {
+                        curve_editor.selected_keyframe = k as usize;
+                    }
+                }
+            }
+
             let (id, rect) = ui.allocate_space(ui.available_size());
 
             // Input handling
@@ -150,17 +658,117 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                 }
             }
 
-            let curve = &mut curve_editor.curve;
+            // Whether a text field (channel rename, numeric keyframe entry, ...) currently
+            // has keyboard focus; while it does, typed characters must not also fire the
+            // single-letter/ctrl shortcuts below.
+            let text_focused = ui.memory().focus().is_some();
+
+            // Global shortcuts: undo/redo and zoom to fit
+            if !text_focused {
+                let input = ui.input();
+                if input.modifiers.command && input.key_pressed(egui::Key::Z) {
+                    if input.modifiers.shift {
+                        curve_editor.redo();
+                    } else {
+                        curve_editor.undo();
+                    }
+                } else if input.modifiers.command && input.key_pressed(egui::Key::Y) {
+                    curve_editor.redo();
+                }
 
-            // Painter and style
-            let color = egui::Color32::RED;
-            let stroke = egui::Stroke::new(1.0, color);
+                if input.key_pressed(egui::Key::F) {
+                    curve_editor.zoom_to_fit();
+                }
+            }
 
             // Curve display range
             let min = curve_editor.display_offset;
             let max = min + curve_editor.display_range;
             let duration = curve_editor.display_range.x.max(0.0);
 
+            // Background grid with adaptive spacing; the step also drives snapping
+            // for keyframe dragging further down.
+            let grid_step_x = nice_grid_step(curve_editor.display_range.x, 10.0);
+            let grid_step_y = nice_grid_step(curve_editor.display_range.y, 8.0);
+            {
+                let grid_stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(45));
+
+                let mut x = (min.x / grid_step_x).ceil() * grid_step_x;
+                while x <= max.x {
+                    let sx = remap(min.x, max.x, x, rect.min.x, rect.max.x);
+                    ui.painter().line_segment(
+                        [egui::Pos2::new(sx, rect.min.y), egui::Pos2::new(sx, rect.max.y)],
+                        grid_stroke,
+                    );
+                    x += grid_step_x;
+                }
+
+                let mut y = (min.y / grid_step_y).ceil() * grid_step_y;
+                while y <= max.y {
+                    let sy = remap(min.y, max.y, y, rect.max.y, rect.min.y);
+                    ui.painter().line_segment(
+                        [egui::Pos2::new(rect.min.x, sy), egui::Pos2::new(rect.max.x, sy)],
+                        grid_stroke,
+                    );
+                    y += grid_step_y;
+                }
+            }
+
+            // Indicative value bounds, drawn as faint horizontal reference lines
+            {
+                let bounds_stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(90));
+                for bound in [curve_editor.min_value, curve_editor.max_value]
+                    .iter()
+                    .filter_map(|b| *b)
+                {
+                    let y = remap(min.y, max.y, bound, rect.max.y, rect.min.y);
+                    ui.painter().line_segment(
+                        [
+                            egui::Pos2::new(rect.min.x, y),
+                            egui::Pos2::new(rect.max.x, y),
+                        ],
+                        bounds_stroke,
+                    );
+                }
+            }
+
+            // Render every visible channel's polyline in its own color; only the
+            // active channel's keyframes are selectable/draggable below.
+            for channel in curve_editor.channels.iter() {
+                if !channel.visible {
+                    continue;
+                }
+
+                let stroke = egui::Stroke::new(1.0, channel.color);
+                let curve = &channel.curve;
+
+                let mut t0 = min.x;
+                let (mut cursor, mut v0) = curve.sample_with_cursor(0, t0);
+                for i in 1..256 {
+                    let t1 = (duration * i as f32 / 255.0) + min.x;
+                    let (next_cursor, v1) = curve.sample_with_cursor(cursor, t1);
+
+                    let x0 = remap(min.x, max.x, t0, rect.min.x, rect.max.x);
+                    let x1 = remap(min.x, max.x, t1, rect.min.x, rect.max.x);
+
+                    let y0 = remap(min.y, max.y, v0, rect.max.y, rect.min.y);
+                    let y1 = remap(min.y, max.y, v1, rect.max.y, rect.min.y);
+
+                    ui.painter().line_segment(
+                        [egui::Pos2::new(x0, y0), egui::Pos2::new(x1, y1)],
+                        stroke,
+                    );
+
+                    v0 = v1;
+                    t0 = t1;
+                    cursor = next_cursor;
+                }
+            }
+
+            let active_channel = curve_editor.active_channel;
+            let color = curve_editor.channels[active_channel].color;
+            let curve = &mut curve_editor.channels[active_channel].curve;
+
             // Context menu to change tangents
             {
                 let popup_id = id.with("popup");
@@ -185,6 +793,9 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                     .selected_keyframe
                     .min(CurveCursor::MAX as usize) as CurveCursor;
 
+                let undo_stack = &mut curve_editor.undo_stack;
+                let redo_stack = &mut curve_editor.redo_stack;
+
                 egui::popup::popup_below_widget(ui, popup_id, &response, |ui| {
                     let selected = (index as usize) < curve.len();
                     ui.set_enabled(selected);
@@ -201,6 +812,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                         .selectable_label(lerp_mode == Interpolation::Step, "Step")
                         .clicked()
                     {
+                        push_undo(active_channel, curve, undo_stack, redo_stack);
                         curve.set_interpolation(index, Interpolation::Step);
                     }
 
@@ -208,6 +820,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                         .selectable_label(lerp_mode == Interpolation::Linear, "Linear")
                         .clicked()
                     {
+                        push_undo(active_channel, curve, undo_stack, redo_stack);
                         curve.set_interpolation(index, Interpolation::Linear);
                     }
 
@@ -218,6 +831,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                         .selectable_label(hermite && tangent_mode == TangentControl::Auto, "Auto")
                         .clicked()
                     {
+                        push_undo(active_channel, curve, undo_stack, redo_stack);
                         curve.set_interpolation(index, Interpolation::Hermite);
                         curve.set_tangent_control(index, TangentControl::Auto);
                     }
@@ -225,6 +839,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                         .selectable_label(hermite && tangent_mode == TangentControl::Free, "Free")
                         .clicked()
                     {
+                        push_undo(active_channel, curve, undo_stack, redo_stack);
                         curve.set_interpolation(index, Interpolation::Hermite);
                         curve.set_tangent_control(index, TangentControl::Free);
                     }
@@ -232,6 +847,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                         .selectable_label(hermite && tangent_mode == TangentControl::Flat, "Flat")
                         .clicked()
                     {
+                        push_undo(active_channel, curve, undo_stack, redo_stack);
                         curve.set_interpolation(index, Interpolation::Hermite);
                         curve.set_tangent_control(index, TangentControl::Flat);
                     }
@@ -242,6 +858,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                         )
                         .clicked()
                     {
+                        push_undo(active_channel, curve, undo_stack, redo_stack);
                         curve.set_interpolation(index, Interpolation::Hermite);
                         curve.set_tangent_control(index, TangentControl::Broken);
                     }
@@ -249,28 +866,7 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                 response.rect = temp;
             }
 
-            // Curve rendering
-            let mut t0 = min.x;
-            let (mut cursor, mut v0) = curve.sample_with_cursor(0, t0);
-            for i in 1..256 {
-                let t1 = (duration * i as f32 / 255.0) + min.x;
-                let (next_cursor, v1) = curve.sample_with_cursor(cursor, t1);
-
-                let x0 = remap(min.x, max.x, t0, rect.min.x, rect.max.x);
-                let x1 = remap(min.x, max.x, t1, rect.min.x, rect.max.x);
-
-                let y0 = remap(min.y, max.y, v0, rect.max.y, rect.min.y);
-                let y1 = remap(min.y, max.y, v1, rect.max.y, rect.min.y);
-
-                ui.painter()
-                    .line_segment([egui::Pos2::new(x0, y0), egui::Pos2::new(x1, y1)], stroke);
-
-                v0 = v1;
-                t0 = t1;
-                cursor = next_cursor;
-            }
-
-            // Curve keyframes
+            // Curve keyframes (active channel only)
             // Appearance
             let tangent_stroke = egui::Stroke::new(1.0, egui::Color32::GRAY);
 
@@ -282,6 +878,9 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                 .pointer
                 .button_down(egui::PointerButton::Primary);
 
+            // Holding Alt snaps dragged keyframes to the background grid
+            let snap = response.ctx.input().modifiers.alt;
+
             if !pointer_down {
                 curve_editor.dragging = false;
             }
@@ -289,7 +888,11 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
             // Insert keyframe
             {
                 let t = remap(rect.min.x, rect.max.x, pointer_position.x, min.x, max.x);
-                let v = curve.sample(t);
+                let v = clamp_value(
+                    curve_editor.min_value,
+                    curve_editor.max_value,
+                    curve.sample(t),
+                );
 
                 let position = egui::Pos2 {
                     x: pointer_position.x,
@@ -299,7 +902,9 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                 ui.painter()
                     .circle_filled(position, 2.0, egui::Color32::GRAY);
 
-                if ui.input().key_pressed(egui::Key::I) {
+                if !text_focused && ui.input().key_pressed(egui::Key::I) {
+                    push_undo(active_channel, curve, &mut curve_editor.undo_stack, &mut curve_editor.redo_stack);
+
                     curve_editor.selected_keyframe = curve
                         .insert()
                         .set_time(t)
@@ -315,13 +920,72 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
             // Delete selected keyframe
             {
                 if curve_editor.selected_keyframe != usize::MAX
+                    && !text_focused
                     && ui.input().key_pressed(egui::Key::D)
                 {
+                    push_undo(active_channel, curve, &mut curve_editor.undo_stack, &mut curve_editor.redo_stack);
+
                     curve.remove(curve_editor.selected_keyframe as CurveCursor);
                     curve_editor.selected_keyframe = usize::MAX;
                 }
             }
 
+            // Copy / paste keyframes and curves
+            {
+                let input = ui.input();
+                let command = input.modifiers.command;
+
+                if text_focused {
+                    // Typing in a field shouldn't also copy/paste keyframes
+                } else if command && input.key_pressed(egui::Key::C) {
+                    let selected = curve_editor.selected_keyframe as CurveCursor;
+                    if (selected as usize) < curve.len() {
+                        let (in_tangent, out_tangent) = curve.get_in_out_tangent(selected);
+                        curve_editor.clipboard_keyframe = Some(CopiedKeyframe {
+                            time: curve.get_time(selected),
+                            value: *curve.get_value(selected),
+                            interpolation: curve.get_interpolation(selected),
+                            tangent_control: curve.get_tangent_control(selected),
+                            in_tangent,
+                            out_tangent,
+                        });
+                        curve_editor.clipboard_curve = None;
+                    } else {
+                        curve_editor.clipboard_curve = Some(curve.clone());
+                        curve_editor.clipboard_keyframe = None;
+                    }
+                } else if command && input.key_pressed(egui::Key::V) {
+                    if let Some(keyframe) = curve_editor.clipboard_keyframe.clone() {
+                        push_undo(active_channel, curve, &mut curve_editor.undo_stack, &mut curve_editor.redo_stack);
+
+                        let t = if rect.contains(pointer_position) {
+                            remap(rect.min.x, rect.max.x, pointer_position.x, min.x, max.x)
+                        } else {
+                            keyframe.time
+                        };
+                        let v = clamp_value(curve_editor.min_value, curve_editor.max_value, keyframe.value);
+                        let index = curve
+                            .insert()
+                            .set_time(t)
+                            .set_value(v)
+                            .set_mode(keyframe.interpolation)
+                            .done();
+
+                        if let Some(index) = index {
+                            curve.set_tangent_control(index, keyframe.tangent_control);
+                            curve.set_in_tangent(index, keyframe.in_tangent);
+                            curve.set_out_tangent(index, keyframe.out_tangent);
+                            curve_editor.selected_keyframe = index as usize;
+                        }
+                    } else if let Some(whole) = curve_editor.clipboard_curve.clone() {
+                        push_undo(active_channel, curve, &mut curve_editor.undo_stack, &mut curve_editor.redo_stack);
+
+                        *curve = whole;
+                        curve_editor.selected_keyframe = usize::MAX;
+                    }
+                }
+            }
+
             // Render keyframes
             for i in 0..curve.len() {
                 let t = curve.get_time(i as CurveCursor);
@@ -342,11 +1006,14 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                     let delta = position - pointer_position;
                     if (delta.y).abs() > 0.5 {
                         let v = remap(rect.max.y, rect.min.y, pointer_position.y, min.y, max.y);
+                        let v = if snap { snap_to_grid(v, grid_step_y) } else { v };
+                        let v = clamp_value(curve_editor.min_value, curve_editor.max_value, v);
                         curve.set_value(i as CurveCursor, v);
                     }
 
                     if (delta.x).abs() > 0.5 {
                         let t = remap(rect.min.x, rect.max.x, pointer_position.x, min.x, max.x);
+                        let t = if snap { snap_to_grid(t, grid_step_x) } else { t };
                         let k = curve.set_time(i as CurveCursor, t);
 
                         if let Some(k) = k {
@@ -399,6 +1066,16 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                                 egui::Color32::GRAY,
                             );
                             if select && pointer_down {
+                                if !selected {
+                                    // Dragging just started, snapshot the curve before
+                                    // the tangent changes
+                                    push_undo(
+                                        active_channel,
+                                        curve,
+                                        &mut curve_editor.undo_stack,
+                                        &mut curve_editor.redo_stack,
+                                    );
+                                }
                                 curve_editor.tangent_drag = TangentEdit::In;
 
                                 let p = egui::Pos2::new(
@@ -429,6 +1106,16 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                                 egui::Color32::GRAY,
                             );
                             if select && pointer_down {
+                                if !selected {
+                                    // Dragging just started, snapshot the curve before
+                                    // the tangent changes
+                                    push_undo(
+                                        active_channel,
+                                        curve,
+                                        &mut curve_editor.undo_stack,
+                                        &mut curve_editor.redo_stack,
+                                    );
+                                }
                                 curve_editor.tangent_drag = TangentEdit::Out;
 
                                 let p = egui::Pos2::new(
@@ -464,6 +1151,11 @@ fn ui_example(mut curve_editor: ResMut<CurveEditor>, egui_context: Res<EguiConte
                     color,
                 );
                 if select {
+                    if press && !curve_editor.dragging {
+                        // Dragging just started, snapshot the curve before it changes
+                        push_undo(active_channel, curve, &mut curve_editor.undo_stack, &mut curve_editor.redo_stack);
+                    }
+
                     curve_editor.selected_keyframe = i;
                     curve_editor.dragging |= press;
                 } else if selected {